@@ -0,0 +1,796 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, token, Address, Env, IntoVal, Symbol,
+};
+
+mod test;
+
+const SECONDS_PER_YEAR: u64 = 31_536_000;
+const BPS_DENOMINATOR: u64 = 10_000;
+/// Fixed-point scale for the cumulative borrow index, RAY-style.
+const SCALE: u64 = 1_000_000_000;
+/// Shares permanently locked on a reserve's first deposit, so the share
+/// price can never be pushed to a level where later deposits round down to
+/// zero shares (the classic first-depositor inflation attack).
+const MINIMUM_LIQUIDITY: u64 = 100;
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    ReserveConfig(Address),
+    TotalDeposits(Address),
+    TotalShares(Address),
+    TotalBorrowed(Address),
+    BorrowIndex(Address),
+    LastAccrualTs(Address),
+    Shares(Address, Address),
+    Loan(Address, Address),
+    Collateral(Address, Address),
+    FlashLoanActive(Address),
+}
+
+/// Market parameters for a single reserve, set once by `add_reserve`.
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveConfig {
+    pub base_rate_bps: u32,
+    pub slope1_bps: u32,
+    pub slope2_bps: u32,
+    pub optimal_utilization_bps: u32,
+    pub premium_bps: u32,
+    pub ltv_bps: u32,
+    pub liquidation_threshold_bps: u32,
+    pub liquidation_bonus_bps: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Loan {
+    pub borrower: Address,
+    pub amount: u64,
+    /// `borrow_index` at origination; repayment owed is
+    /// `amount * current_index / index_snapshot`.
+    pub index_snapshot: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolState {
+    pub total_deposits: u64,
+    pub total_shares: u64,
+    pub total_borrowed: u64,
+}
+
+/// A borrower's collateral/debt position. `health_factor_bps` is the
+/// collateral value weighted by the liquidation threshold divided by the
+/// outstanding debt, expressed in bps (10_000 == 1.0x); a borrower with no
+/// debt is reported as `u32::MAX` since there is nothing to liquidate.
+#[derive(Clone)]
+#[contracttype]
+pub struct Obligation {
+    pub collateral: u64,
+    pub debt: u64,
+    pub health_factor_bps: u32,
+}
+
+/// The current two-slope ("kink") interest rates, in bps.
+#[derive(Clone)]
+#[contracttype]
+pub struct Rates {
+    pub utilization_bps: u32,
+    pub borrow_rate_bps: u32,
+    pub supply_rate_bps: u32,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidAmount = 3,
+    InsufficientShares = 4,
+    InsufficientLiquidity = 5,
+    LoanAlreadyExists = 6,
+    LoanNotFound = 7,
+    FlashLoanReentrant = 8,
+    FlashLoanNotRepaid = 9,
+    ExceedsLtv = 10,
+    NotLiquidatable = 11,
+    ReserveAlreadyExists = 12,
+    ReserveNotFound = 13,
+    NotAdmin = 14,
+    InsufficientInitialDeposit = 15,
+    DepositTooSmall = 16,
+    InvalidReserveConfig = 17,
+}
+
+#[contract]
+pub struct LendingContract;
+
+#[contractimpl]
+impl LendingContract {
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        Ok(())
+    }
+
+    /// Adds a new reserve for `token`, admin-gated and one-time per token,
+    /// mirroring the `AlreadyInitialized` guard above. Each reserve keeps
+    /// its own shares, deposits/borrows, loans, and interest accrual, so a
+    /// single deployed contract can run several independent markets.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_reserve(
+        env: Env,
+        admin: Address,
+        token: Address,
+        base_rate_bps: u32,
+        slope1_bps: u32,
+        slope2_bps: u32,
+        optimal_utilization_bps: u32,
+        premium_bps: u32,
+        ltv_bps: u32,
+        liquidation_threshold_bps: u32,
+        liquidation_bonus_bps: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        if stored_admin != admin {
+            return Err(Error::NotAdmin);
+        }
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::ReserveConfig(token.clone()))
+        {
+            return Err(Error::ReserveAlreadyExists);
+        }
+        if optimal_utilization_bps == 0 || optimal_utilization_bps >= BPS_DENOMINATOR as u32 {
+            return Err(Error::InvalidReserveConfig);
+        }
+
+        let config = ReserveConfig {
+            base_rate_bps,
+            slope1_bps,
+            slope2_bps,
+            optimal_utilization_bps,
+            premium_bps,
+            ltv_bps,
+            liquidation_threshold_bps,
+            liquidation_bonus_bps,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::ReserveConfig(token.clone()), &config);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalDeposits(token.clone()), &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalShares(token.clone()), &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalBorrowed(token.clone()), &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::BorrowIndex(token.clone()), &SCALE);
+        env.storage().instance().set(
+            &DataKey::LastAccrualTs(token),
+            &env.ledger().timestamp(),
+        );
+
+        Ok(())
+    }
+
+    pub fn deposit_collateral(
+        env: Env,
+        borrower: Address,
+        token: Address,
+        amount: u64,
+    ) -> Result<(), Error> {
+        if amount == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        borrower.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+
+        token::Client::new(&env, &token).transfer(
+            &borrower,
+            &env.current_contract_address(),
+            &(amount as i128),
+        );
+
+        let collateral = Self::collateral_of(&env, &token, &borrower);
+        env.storage().persistent().set(
+            &DataKey::Collateral(token, borrower),
+            &(collateral + amount),
+        );
+
+        Ok(())
+    }
+
+    pub fn deposit(
+        env: Env,
+        depositor: Address,
+        token: Address,
+        amount: u64,
+    ) -> Result<u64, Error> {
+        if amount == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        depositor.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+
+        let total_deposits = Self::total_deposits(&env, &token);
+        let total_shares = Self::total_shares(&env, &token);
+        let is_first_deposit = total_shares == 0;
+
+        let shares = if is_first_deposit {
+            if amount <= MINIMUM_LIQUIDITY {
+                return Err(Error::InsufficientInitialDeposit);
+            }
+            // Sacrifice MINIMUM_LIQUIDITY shares, locked forever below, so
+            // the share price can't be pushed to where later deposits
+            // round down to zero.
+            amount - MINIMUM_LIQUIDITY
+        } else {
+            // proportional to the existing share price
+            ((amount as u128) * (total_shares as u128) / (total_deposits as u128)) as u64
+        };
+        if shares == 0 {
+            return Err(Error::DepositTooSmall);
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &depositor,
+            &env.current_contract_address(),
+            &(amount as i128),
+        );
+
+        let minted_shares = if is_first_deposit { amount } else { shares };
+        env.storage().instance().set(
+            &DataKey::TotalDeposits(token.clone()),
+            &(total_deposits + amount),
+        );
+        env.storage().instance().set(
+            &DataKey::TotalShares(token.clone()),
+            &(total_shares + minted_shares),
+        );
+
+        if is_first_deposit {
+            env.storage().persistent().set(
+                &DataKey::Shares(token.clone(), env.current_contract_address()),
+                &MINIMUM_LIQUIDITY,
+            );
+        }
+
+        let depositor_shares = Self::shares_of(&env, &token, &depositor);
+        env.storage().persistent().set(
+            &DataKey::Shares(token, depositor),
+            &(depositor_shares + shares),
+        );
+
+        Ok(shares)
+    }
+
+    pub fn withdraw(
+        env: Env,
+        depositor: Address,
+        token: Address,
+        shares: u64,
+    ) -> Result<u64, Error> {
+        if shares == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        depositor.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+
+        let depositor_shares = Self::shares_of(&env, &token, &depositor);
+        if shares > depositor_shares {
+            return Err(Error::InsufficientShares);
+        }
+
+        let total_deposits = Self::total_deposits(&env, &token);
+        let total_shares = Self::total_shares(&env, &token);
+        let amount = ((shares as u128) * (total_deposits as u128) / (total_shares as u128)) as u64;
+
+        if amount > Self::available_liquidity(env.clone(), token.clone()) {
+            return Err(Error::InsufficientLiquidity);
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &depositor,
+            &(amount as i128),
+        );
+
+        env.storage().instance().set(
+            &DataKey::TotalDeposits(token.clone()),
+            &(total_deposits - amount),
+        );
+        env.storage().instance().set(
+            &DataKey::TotalShares(token.clone()),
+            &(total_shares - shares),
+        );
+        env.storage().persistent().set(
+            &DataKey::Shares(token, depositor),
+            &(depositor_shares - shares),
+        );
+
+        Ok(amount)
+    }
+
+    pub fn borrow(
+        env: Env,
+        borrower: Address,
+        token: Address,
+        amount: u64,
+    ) -> Result<(), Error> {
+        if amount == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        borrower.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Loan(token.clone(), borrower.clone()))
+        {
+            return Err(Error::LoanAlreadyExists);
+        }
+
+        let collateral = Self::collateral_of(&env, &token, &borrower);
+        let ltv_bps = Self::reserve_config(&env, &token).ltv_bps as u128;
+        let max_borrowable =
+            ((collateral as u128 * ltv_bps) / BPS_DENOMINATOR as u128) as u64;
+        if amount > max_borrowable {
+            return Err(Error::ExceedsLtv);
+        }
+        if amount > Self::available_liquidity(env.clone(), token.clone()) {
+            return Err(Error::InsufficientLiquidity);
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &borrower,
+            &(amount as i128),
+        );
+
+        let total_borrowed = Self::total_borrowed(&env, &token);
+        env.storage().instance().set(
+            &DataKey::TotalBorrowed(token.clone()),
+            &(total_borrowed + amount),
+        );
+
+        let loan = Loan {
+            borrower: borrower.clone(),
+            amount,
+            index_snapshot: Self::borrow_index(&env, &token),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Loan(token, borrower), &loan);
+
+        Ok(())
+    }
+
+    pub fn repay(env: Env, borrower: Address, token: Address) -> Result<u64, Error> {
+        borrower.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+        Self::settle_debt(&env, &token, &borrower, &borrower)
+    }
+
+    /// Repays `borrower`'s debt out of `liquidator`'s funds and seizes their
+    /// collateral at a discount, when `borrower`'s health factor has fallen
+    /// below 1.0 (`health_factor_bps < 10_000`).
+    pub fn liquidate(
+        env: Env,
+        liquidator: Address,
+        borrower: Address,
+        token: Address,
+    ) -> Result<u64, Error> {
+        liquidator.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+
+        let obligation = Self::get_obligation(env.clone(), borrower.clone(), token.clone());
+        if obligation.health_factor_bps >= BPS_DENOMINATOR as u32 {
+            return Err(Error::NotLiquidatable);
+        }
+
+        let repaid = Self::settle_debt(&env, &token, &borrower, &liquidator)?;
+
+        let bonus_bps = Self::reserve_config(&env, &token).liquidation_bonus_bps as u128;
+        let seize = core::cmp::min(
+            ((repaid as u128 * (BPS_DENOMINATOR as u128 + bonus_bps)) / BPS_DENOMINATOR as u128)
+                as u64,
+            obligation.collateral,
+        );
+
+        env.storage().persistent().set(
+            &DataKey::Collateral(token.clone(), borrower.clone()),
+            &(obligation.collateral - seize),
+        );
+
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &liquidator,
+            &(seize as i128),
+        );
+
+        Ok(seize)
+    }
+
+    /// Settles `borrower`'s full outstanding debt in `token`'s reserve out
+    /// of `payer`'s funds. Assumes `accrue` has already run so
+    /// `total_borrowed` reflects the index as of now; this loan's `owed`
+    /// amount is then simply retired from that freshly-accrued total (the
+    /// interest itself was already credited to `total_deposits` by
+    /// `accrue`).
+    fn settle_debt(
+        env: &Env,
+        token: &Address,
+        borrower: &Address,
+        payer: &Address,
+    ) -> Result<u64, Error> {
+        let loan: Loan = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Loan(token.clone(), borrower.clone()))
+            .ok_or(Error::LoanNotFound)?;
+
+        let owed = Self::get_repayment_amount(env.clone(), borrower.clone(), token.clone());
+
+        token::Client::new(env, token).transfer(
+            payer,
+            &env.current_contract_address(),
+            &(owed as i128),
+        );
+
+        let total_borrowed = Self::total_borrowed(env, token);
+        env.storage().instance().set(
+            &DataKey::TotalBorrowed(token.clone()),
+            &total_borrowed.saturating_sub(owed),
+        );
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Loan(token.clone(), borrower.clone()));
+
+        Ok(owed)
+    }
+
+    /// Transfers `amount` of `token`'s liquidity to `receiver`, invokes its
+    /// `on_flash_loan(token, amount, premium)` callback, and requires that
+    /// `amount + premium` has come back to the pool by the time the
+    /// callback returns. The premium accrues to that reserve's
+    /// `total_deposits`, the same way borrow interest grows the share
+    /// exchange rate.
+    pub fn flash_loan(
+        env: Env,
+        borrower: Address,
+        receiver: Address,
+        token: Address,
+        amount: u64,
+    ) -> Result<(), Error> {
+        if amount == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        borrower.require_auth();
+        Self::require_reserve(&env, &token)?;
+        Self::accrue(&env, &token);
+
+        if env
+            .storage()
+            .instance()
+            .get(&DataKey::FlashLoanActive(token.clone()))
+            .unwrap_or(false)
+        {
+            return Err(Error::FlashLoanReentrant);
+        }
+        if amount > Self::available_liquidity(env.clone(), token.clone()) {
+            return Err(Error::InsufficientLiquidity);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        let premium_bps = Self::reserve_config(&env, &token).premium_bps as u128;
+        let premium =
+            ((amount as u128 * premium_bps) / BPS_DENOMINATOR as u128) as u64;
+
+        let balance_before = token_client.balance(&env.current_contract_address());
+
+        env.storage()
+            .instance()
+            .set(&DataKey::FlashLoanActive(token.clone()), &true);
+
+        token_client.transfer(
+            &env.current_contract_address(),
+            &receiver,
+            &(amount as i128),
+        );
+
+        env.invoke_contract::<()>(
+            &receiver,
+            &Symbol::new(&env, "on_flash_loan"),
+            (token.clone(), amount, premium).into_val(&env),
+        );
+
+        env.storage()
+            .instance()
+            .set(&DataKey::FlashLoanActive(token.clone()), &false);
+
+        let balance_after = token_client.balance(&env.current_contract_address());
+        if balance_after < balance_before + premium as i128 {
+            return Err(Error::FlashLoanNotRepaid);
+        }
+
+        let total_deposits = Self::total_deposits(&env, &token);
+        env.storage().instance().set(
+            &DataKey::TotalDeposits(token),
+            &(total_deposits + premium),
+        );
+
+        Ok(())
+    }
+
+    pub fn get_loan(env: Env, borrower: Address, token: Address) -> Option<Loan> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Loan(token, borrower))
+    }
+
+    /// A pure read: reports what `borrower` owes right now in `token`'s
+    /// reserve, using the index as it would stand after a fresh `accrue`,
+    /// without mutating storage.
+    pub fn get_repayment_amount(env: Env, borrower: Address, token: Address) -> u64 {
+        let loan: Loan = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::Loan(token.clone(), borrower))
+        {
+            Some(loan) => loan,
+            None => return 0,
+        };
+
+        let current_index = Self::current_index(&env, &token) as u128;
+        ((loan.amount as u128 * current_index) / loan.index_snapshot as u128) as u64
+    }
+
+    pub fn get_shares_of(env: Env, depositor: Address, token: Address) -> u64 {
+        Self::shares_of(&env, &token, &depositor)
+    }
+
+    pub fn get_obligation(env: Env, borrower: Address, token: Address) -> Obligation {
+        let collateral = Self::collateral_of(&env, &token, &borrower);
+        let debt = Self::get_repayment_amount(env.clone(), borrower, token.clone());
+        let health_factor_bps = if debt == 0 {
+            u32::MAX
+        } else {
+            let threshold_bps =
+                Self::reserve_config(&env, &token).liquidation_threshold_bps as u128;
+            ((collateral as u128 * threshold_bps) / debt as u128)
+                .min(u32::MAX as u128) as u32
+        };
+
+        Obligation {
+            collateral,
+            debt,
+            health_factor_bps,
+        }
+    }
+
+    pub fn available_liquidity(env: Env, token: Address) -> u64 {
+        Self::total_deposits(&env, &token) - Self::total_borrowed(&env, &token)
+    }
+
+    pub fn get_pool_state(env: Env, token: Address) -> PoolState {
+        PoolState {
+            total_deposits: Self::total_deposits(&env, &token),
+            total_shares: Self::total_shares(&env, &token),
+            total_borrowed: Self::total_borrowed(&env, &token),
+        }
+    }
+
+    /// The current utilization-driven borrow rate for `token`'s reserve and
+    /// the supply rate it implies (`borrow_rate * utilization / 10_000`),
+    /// both in bps.
+    pub fn get_current_rates(env: Env, token: Address) -> Rates {
+        let utilization_bps = Self::utilization_bps(
+            Self::total_borrowed(&env, &token),
+            Self::total_deposits(&env, &token),
+        );
+        let borrow_rate_bps = Self::borrow_rate_bps(&env, &token, utilization_bps);
+        let supply_rate_bps =
+            ((borrow_rate_bps as u64 * utilization_bps as u64) / BPS_DENOMINATOR) as u32;
+
+        Rates {
+            utilization_bps,
+            borrow_rate_bps,
+            supply_rate_bps,
+        }
+    }
+
+    /// Accrues interest for `token`'s reserve since the last mutating call:
+    /// grows `borrow_index` by the current utilization-based borrow rate
+    /// over the elapsed time, scales `total_borrowed` by the same factor,
+    /// and credits the difference to `total_deposits`.
+    fn accrue(env: &Env, token: &Address) {
+        let now = env.ledger().timestamp();
+        let last_accrual_ts: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastAccrualTs(token.clone()))
+            .unwrap_or(now);
+        let delta = now - last_accrual_ts;
+        if delta == 0 {
+            return;
+        }
+
+        let old_index = Self::borrow_index(env, token);
+        let total_borrowed = Self::total_borrowed(env, token);
+        let total_deposits = Self::total_deposits(env, token);
+        let rate_bps =
+            Self::borrow_rate_bps(env, token, Self::utilization_bps(total_borrowed, total_deposits));
+        let new_index = Self::apply_growth(old_index, Self::growth_factor(rate_bps, delta));
+
+        let new_total_borrowed =
+            ((total_borrowed as u128 * new_index as u128) / old_index as u128) as u64;
+        let accrued_interest = new_total_borrowed - total_borrowed;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalBorrowed(token.clone()), &new_total_borrowed);
+        env.storage().instance().set(
+            &DataKey::TotalDeposits(token.clone()),
+            &(total_deposits + accrued_interest),
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::BorrowIndex(token.clone()), &new_index);
+        env.storage()
+            .instance()
+            .set(&DataKey::LastAccrualTs(token.clone()), &now);
+    }
+
+    /// What `token`'s `borrow_index` would be if accrued right now, without
+    /// writing to storage — used by read-only calls like
+    /// `get_repayment_amount`.
+    fn current_index(env: &Env, token: &Address) -> u64 {
+        let now = env.ledger().timestamp();
+        let last_accrual_ts: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastAccrualTs(token.clone()))
+            .unwrap_or(now);
+        let delta = now - last_accrual_ts;
+        let index = Self::borrow_index(env, token);
+        if delta == 0 {
+            return index;
+        }
+
+        let rate_bps = Self::borrow_rate_bps(
+            env,
+            token,
+            Self::utilization_bps(Self::total_borrowed(env, token), Self::total_deposits(env, token)),
+        );
+        Self::apply_growth(index, Self::growth_factor(rate_bps, delta))
+    }
+
+    /// `U = total_borrowed * 10_000 / total_deposits`, in bps.
+    fn utilization_bps(total_borrowed: u64, total_deposits: u64) -> u32 {
+        if total_deposits == 0 {
+            return 0;
+        }
+        ((total_borrowed as u128 * BPS_DENOMINATOR as u128) / total_deposits as u128) as u32
+    }
+
+    /// Two-slope ("kink") borrow rate: linear growth via `slope1` up to
+    /// `optimal_utilization_bps`, then a steeper `slope2` beyond it, so the
+    /// pool's cost of borrowing rises sharply as liquidity dries up.
+    fn borrow_rate_bps(env: &Env, token: &Address, utilization_bps: u32) -> u32 {
+        let config = Self::reserve_config(env, token);
+        let base = config.base_rate_bps as u64;
+        let slope1 = config.slope1_bps as u64;
+        let slope2 = config.slope2_bps as u64;
+        let optimal = config.optimal_utilization_bps as u64;
+        let u = utilization_bps as u64;
+
+        if u <= optimal {
+            base + (slope1 * u) / optimal
+        } else {
+            base + slope1 + (slope2 * (u - optimal)) / (BPS_DENOMINATOR - optimal)
+        }
+        .min(u32::MAX as u64) as u32
+    }
+
+    /// `SCALE + apy_bps * SCALE * delta / (10_000 * SECONDS_PER_YEAR)`, the
+    /// per-period multiplicative growth factor in `SCALE`-fixed point.
+    fn growth_factor(apy_bps: u32, delta: u64) -> u128 {
+        let scale = SCALE as u128;
+        scale
+            + (apy_bps as u128 * scale * delta as u128)
+                / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128)
+    }
+
+    fn apply_growth(index: u64, growth: u128) -> u64 {
+        ((index as u128 * growth) / SCALE as u128) as u64
+    }
+
+    fn require_reserve(env: &Env, token: &Address) -> Result<(), Error> {
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::ReserveConfig(token.clone()))
+        {
+            Ok(())
+        } else {
+            Err(Error::ReserveNotFound)
+        }
+    }
+
+    fn reserve_config(env: &Env, token: &Address) -> ReserveConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::ReserveConfig(token.clone()))
+            .unwrap()
+    }
+
+    fn collateral_of(env: &Env, token: &Address, borrower: &Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Collateral(token.clone(), borrower.clone()))
+            .unwrap_or(0)
+    }
+
+    fn total_deposits(env: &Env, token: &Address) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalDeposits(token.clone()))
+            .unwrap_or(0)
+    }
+
+    fn total_shares(env: &Env, token: &Address) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalShares(token.clone()))
+            .unwrap_or(0)
+    }
+
+    fn total_borrowed(env: &Env, token: &Address) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalBorrowed(token.clone()))
+            .unwrap_or(0)
+    }
+
+    fn borrow_index(env: &Env, token: &Address) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::BorrowIndex(token.clone()))
+            .unwrap_or(SCALE)
+    }
+
+    fn shares_of(env: &Env, token: &Address, depositor: &Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Shares(token.clone(), depositor.clone()))
+            .unwrap_or(0)
+    }
+}
@@ -26,7 +26,8 @@ fn mint_to(env: &Env, token: &Address, to: &Address, amount: i128) {
 }
 
 // ─────────────────────────────────────────────────
-// Setup: returns (client, token_addr, admin)
+// Setup: returns (client, token_addr, admin) with a single reserve for
+// token_addr already added.
 // ─────────────────────────────────────────────────
 fn setup(env: &Env) -> (LendingContractClient<'_>, Address, Address) {
     let admin = Address::generate(env);
@@ -34,11 +35,76 @@ fn setup(env: &Env) -> (LendingContractClient<'_>, Address, Address) {
 
     let contract_id = env.register_contract(None, LendingContract);
     let client = LendingContractClient::new(env, &contract_id);
-    client.initialize(&admin, &token_addr, &1000u32); // 10% APY
+    client.initialize(&admin);
+    // 10% base rate flat below 80% utilization (slope1=0), steep slope2 above it,
+    // 0.5% flash loan premium, 80% LTV, 85% liquidation threshold, 5% liquidation bonus
+    client.add_reserve(
+        &admin,
+        &token_addr,
+        &1000u32,
+        &0u32,
+        &10_000u32,
+        &8000u32,
+        &50u32,
+        &8000u32,
+        &8500u32,
+        &500u32,
+    );
 
     (client, token_addr, admin)
 }
 
+// Deposits `amount * 2` collateral on behalf of `borrower` so that, at the
+// 80% LTV configured in `setup`, borrowing up to `amount` never hits the
+// LTV cap — existing borrow tests only care about the liquidity checks.
+fn fund_collateral(
+    env: &Env,
+    client: &LendingContractClient<'_>,
+    token_addr: &Address,
+    borrower: &Address,
+    amount: u64,
+) {
+    mint_to(env, token_addr, borrower, (amount * 2) as i128);
+    client.deposit_collateral(borrower, token_addr, &(amount * 2));
+}
+
+// ─────────────────────────────────────────────────
+// Mock flash loan receiver
+// ─────────────────────────────────────────────────
+mod flash_receiver {
+    use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+    #[contracttype]
+    pub enum DataKey {
+        Pool,
+        Repay,
+    }
+
+    #[contract]
+    pub struct FlashBorrower;
+
+    #[contractimpl]
+    impl FlashBorrower {
+        pub fn init(env: Env, pool: Address, repay: bool) {
+            env.storage().instance().set(&DataKey::Pool, &pool);
+            env.storage().instance().set(&DataKey::Repay, &repay);
+        }
+
+        pub fn on_flash_loan(env: Env, token: Address, amount: u64, premium: u64) {
+            let repay: bool = env.storage().instance().get(&DataKey::Repay).unwrap();
+            if !repay {
+                return;
+            }
+            let pool: Address = env.storage().instance().get(&DataKey::Pool).unwrap();
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &pool,
+                &((amount + premium) as i128),
+            );
+        }
+    }
+}
+
 // ─────────────────────────────────────────────────
 // Tests
 // ─────────────────────────────────────────────────
@@ -47,13 +113,96 @@ fn setup(env: &Env) -> (LendingContractClient<'_>, Address, Address) {
 fn test_initialize_once() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, token_addr, admin) = setup(&env);
+    let (client, _token_addr, admin) = setup(&env);
 
     // Second init must fail
-    let result = client.try_initialize(&admin, &token_addr, &1000u32);
+    let result = client.try_initialize(&admin);
     assert!(result.is_err());
 }
 
+#[test]
+fn test_add_reserve_once_per_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, admin) = setup(&env);
+
+    // Adding the same reserve twice must fail
+    let result = client.try_add_reserve(
+        &admin,
+        &token_addr,
+        &1000u32,
+        &0u32,
+        &10_000u32,
+        &8000u32,
+        &50u32,
+        &8000u32,
+        &8500u32,
+        &500u32,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_add_reserve_rejects_non_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token_addr, _admin) = setup(&env);
+
+    let not_admin = Address::generate(&env);
+    let other_token = create_token_addr(&env);
+    let result = client.try_add_reserve(
+        &not_admin,
+        &other_token,
+        &1000u32,
+        &0u32,
+        &10_000u32,
+        &8000u32,
+        &50u32,
+        &8000u32,
+        &8500u32,
+        &500u32,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_second_reserve_is_an_independent_market() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, admin) = setup(&env);
+
+    let other_token = create_token_addr(&env);
+    client.add_reserve(
+        &admin,
+        &other_token,
+        &1000u32,
+        &0u32,
+        &10_000u32,
+        &8000u32,
+        &50u32,
+        &8000u32,
+        &8500u32,
+        &500u32,
+    );
+
+    let depositor = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &other_token, &depositor, 10_000);
+
+    client.deposit(&depositor, &token_addr, &1000u64);
+
+    // The second reserve is untouched by activity in the first.
+    let pool = client.get_pool_state(&other_token);
+    assert_eq!(pool.total_deposits, 0);
+    assert_eq!(client.get_shares_of(&depositor, &other_token), 0);
+
+    client.deposit(&depositor, &other_token, &500u64);
+    let pool = client.get_pool_state(&token_addr);
+    assert_eq!(pool.total_deposits, 1000);
+    let pool = client.get_pool_state(&other_token);
+    assert_eq!(pool.total_deposits, 500);
+}
+
 #[test]
 fn test_deposit_mints_shares() {
     let env = Env::default();
@@ -63,12 +212,15 @@ fn test_deposit_mints_shares() {
     let depositor = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
 
-    let shares = client.deposit(&depositor, &1000u64);
-    // First deposit: 1:1 ratio
-    assert_eq!(shares, 1000u64);
-    assert_eq!(client.get_shares_of(&depositor), 1000u64);
+    let shares = client.deposit(&depositor, &token_addr, &1000u64);
+    // First deposit: 1:1 ratio, minus the locked MINIMUM_LIQUIDITY dead shares
+    assert_eq!(shares, 1000 - MINIMUM_LIQUIDITY);
+    assert_eq!(
+        client.get_shares_of(&depositor, &token_addr),
+        1000 - MINIMUM_LIQUIDITY
+    );
 
-    let pool = client.get_pool_state();
+    let pool = client.get_pool_state(&token_addr);
     assert_eq!(pool.total_deposits, 1000);
     assert_eq!(pool.total_shares, 1000);
     assert_eq!(pool.total_borrowed, 0);
@@ -86,13 +238,13 @@ fn test_second_deposit_proportional_shares() {
     mint_to(&env, &token_addr, &depositor2, 10_000);
 
     // First deposit: 1000 tokens → 1000 shares
-    client.deposit(&depositor1, &1000u64);
+    client.deposit(&depositor1, &token_addr, &1000u64);
 
     // Second deposit: same ratio → 500 tokens → 500 shares
-    let shares2 = client.deposit(&depositor2, &500u64);
+    let shares2 = client.deposit(&depositor2, &token_addr, &500u64);
     assert_eq!(shares2, 500u64);
 
-    let pool = client.get_pool_state();
+    let pool = client.get_pool_state(&token_addr);
     assert_eq!(pool.total_deposits, 1500);
     assert_eq!(pool.total_shares, 1500);
 }
@@ -106,19 +258,23 @@ fn test_withdraw_burns_shares_and_returns_tokens() {
     let depositor = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
 
-    client.deposit(&depositor, &1000u64);
+    let minted = client.deposit(&depositor, &token_addr, &1000u64);
     let balance_before = tok_client(&env, &token_addr).balance(&depositor);
 
-    // Withdraw 500 shares → should get 500 tokens back
-    let returned = client.withdraw(&depositor, &500u64);
+    // Withdraw 500 shares → should get 500 tokens back (1:1, since the
+    // locked dead shares don't change the share price)
+    let returned = client.withdraw(&depositor, &token_addr, &500u64);
     assert_eq!(returned, 500u64);
     assert_eq!(
         tok_client(&env, &token_addr).balance(&depositor),
         balance_before + 500
     );
-    assert_eq!(client.get_shares_of(&depositor), 500u64);
+    assert_eq!(
+        client.get_shares_of(&depositor, &token_addr),
+        minted - 500
+    );
 
-    let pool = client.get_pool_state();
+    let pool = client.get_pool_state(&token_addr);
     assert_eq!(pool.total_deposits, 500);
     assert_eq!(pool.total_shares, 500);
 }
@@ -131,10 +287,10 @@ fn test_withdraw_fails_not_enough_shares() {
 
     let depositor = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
-    client.deposit(&depositor, &1000u64);
+    client.deposit(&depositor, &token_addr, &1000u64);
 
     // Try to withdraw more shares than owned
-    let result = client.try_withdraw(&depositor, &2000u64);
+    let result = client.try_withdraw(&depositor, &token_addr, &2000u64);
     assert!(result.is_err());
 }
 
@@ -147,22 +303,23 @@ fn test_borrow_reduces_available_liquidity() {
     let depositor = Address::generate(&env);
     let borrower = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
-    client.deposit(&depositor, &1000u64);
+    client.deposit(&depositor, &token_addr, &1000u64);
 
     let borrow_amount = 400u64;
+    fund_collateral(&env, &client, &token_addr, &borrower, borrow_amount);
     let balance_before = tok_client(&env, &token_addr).balance(&borrower);
-    client.borrow(&borrower, &borrow_amount);
+    client.borrow(&borrower, &token_addr, &borrow_amount);
 
     assert_eq!(
         tok_client(&env, &token_addr).balance(&borrower),
         balance_before + 400
     );
 
-    let pool = client.get_pool_state();
+    let pool = client.get_pool_state(&token_addr);
     assert_eq!(pool.total_borrowed, 400);
     assert_eq!(pool.total_deposits, 1000);
 
-    assert_eq!(client.available_liquidity(), 600u64);
+    assert_eq!(client.available_liquidity(&token_addr), 600u64);
 }
 
 #[test]
@@ -173,9 +330,10 @@ fn test_borrow_fails_if_insufficient_liquidity() {
 
     let depositor = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
-    client.deposit(&depositor, &1000u64);
+    client.deposit(&depositor, &token_addr, &1000u64);
+    fund_collateral(&env, &client, &token_addr, &depositor, 1001u64);
 
-    let result = client.try_borrow(&depositor, &1001u64);
+    let result = client.try_borrow(&depositor, &token_addr, &1001u64);
     assert!(result.is_err());
 }
 
@@ -188,11 +346,12 @@ fn test_borrow_fails_with_existing_loan() {
     let depositor = Address::generate(&env);
     let borrower = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
-    client.deposit(&depositor, &1000u64);
-    client.borrow(&borrower, &200u64);
+    client.deposit(&depositor, &token_addr, &1000u64);
+    fund_collateral(&env, &client, &token_addr, &borrower, 200u64);
+    client.borrow(&borrower, &token_addr, &200u64);
 
     // Second borrow should fail
-    let result = client.try_borrow(&borrower, &100u64);
+    let result = client.try_borrow(&borrower, &token_addr, &100u64);
     assert!(result.is_err());
 }
 
@@ -207,21 +366,22 @@ fn test_repay_restores_liquidity() {
     mint_to(&env, &token_addr, &depositor, 10_000);
     mint_to(&env, &token_addr, &borrower, 10_000); // pre-fund borrower for repayment
 
-    client.deposit(&depositor, &1000u64);
-    client.borrow(&borrower, &400u64);
+    client.deposit(&depositor, &token_addr, &1000u64);
+    fund_collateral(&env, &client, &token_addr, &borrower, 400u64);
+    client.borrow(&borrower, &token_addr, &400u64);
 
-    assert_eq!(client.available_liquidity(), 600u64);
+    assert_eq!(client.available_liquidity(&token_addr), 600u64);
 
-    let repaid = client.repay(&borrower);
+    let repaid = client.repay(&borrower, &token_addr);
     assert_eq!(repaid, 400u64);
 
-    let pool = client.get_pool_state();
+    let pool = client.get_pool_state(&token_addr);
     assert_eq!(pool.total_borrowed, 0);
     assert_eq!(pool.total_deposits, 1000);
-    assert_eq!(client.available_liquidity(), 1000u64);
+    assert_eq!(client.available_liquidity(&token_addr), 1000u64);
 
     // Loan should be gone
-    let loan = client.get_loan(&borrower);
+    let loan = client.get_loan(&borrower, &token_addr);
     assert!(loan.is_none());
 }
 
@@ -229,9 +389,9 @@ fn test_repay_restores_liquidity() {
 fn test_repay_fails_with_no_loan() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _token_addr, admin) = setup(&env);
+    let (client, token_addr, admin) = setup(&env);
 
-    let result = client.try_repay(&admin);
+    let result = client.try_repay(&admin, &token_addr);
     assert!(result.is_err());
 }
 
@@ -245,15 +405,16 @@ fn test_withdraw_fails_if_funds_are_borrowed() {
     let borrower = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
 
-    client.deposit(&depositor, &1000u64);
-    client.borrow(&borrower, &900u64); // only 100 tokens left un-borrowed
+    client.deposit(&depositor, &token_addr, &1000u64);
+    fund_collateral(&env, &client, &token_addr, &borrower, 900u64);
+    client.borrow(&borrower, &token_addr, &900u64); // only 100 tokens left un-borrowed
 
     // Depositor tries to withdraw 500 → only 100 available
-    let result = client.try_withdraw(&depositor, &500u64);
+    let result = client.try_withdraw(&depositor, &token_addr, &500u64);
     assert!(result.is_err());
 
     // Can still withdraw 100's worth of shares
-    assert!(client.try_withdraw(&depositor, &100u64).is_ok());
+    assert!(client.try_withdraw(&depositor, &token_addr, &100u64).is_ok());
 }
 
 #[test]
@@ -267,26 +428,27 @@ fn test_available_liquidity_before_and_after() {
     mint_to(&env, &token_addr, &depositor, 10_000);
     mint_to(&env, &token_addr, &borrower, 10_000);
 
-    assert_eq!(client.available_liquidity(), 0u64);
+    assert_eq!(client.available_liquidity(&token_addr), 0u64);
 
-    client.deposit(&depositor, &2000u64);
-    assert_eq!(client.available_liquidity(), 2000u64);
+    client.deposit(&depositor, &token_addr, &2000u64);
+    assert_eq!(client.available_liquidity(&token_addr), 2000u64);
 
-    client.borrow(&borrower, &1500u64);
-    assert_eq!(client.available_liquidity(), 500u64);
+    fund_collateral(&env, &client, &token_addr, &borrower, 1500u64);
+    client.borrow(&borrower, &token_addr, &1500u64);
+    assert_eq!(client.available_liquidity(&token_addr), 500u64);
 
-    client.repay(&borrower);
-    assert_eq!(client.available_liquidity(), 2000u64);
+    client.repay(&borrower, &token_addr);
+    assert_eq!(client.available_liquidity(&token_addr), 2000u64);
 }
 
 #[test]
 fn test_get_loan_returns_none_when_no_loan() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _token_addr, _admin) = setup(&env);
+    let (client, token_addr, _admin) = setup(&env);
 
     let no_loan_addr = Address::generate(&env);
-    let loan = client.get_loan(&no_loan_addr);
+    let loan = client.get_loan(&no_loan_addr, &token_addr);
     assert!(loan.is_none());
 }
 
@@ -300,10 +462,11 @@ fn test_get_loan_returns_record_when_active() {
     let borrower = Address::generate(&env);
     mint_to(&env, &token_addr, &depositor, 10_000);
 
-    client.deposit(&depositor, &1000u64);
-    client.borrow(&borrower, &300u64);
+    client.deposit(&depositor, &token_addr, &1000u64);
+    fund_collateral(&env, &client, &token_addr, &borrower, 300u64);
+    client.borrow(&borrower, &token_addr, &300u64);
 
-    let loan = client.get_loan(&borrower).unwrap();
+    let loan = client.get_loan(&borrower, &token_addr).unwrap();
     assert_eq!(loan.amount, 300u64);
     assert_eq!(loan.borrower, borrower);
 }
@@ -312,12 +475,14 @@ fn test_get_loan_returns_record_when_active() {
 fn test_invalid_amounts_rejected() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _token_addr, admin) = setup(&env);
+    let (client, token_addr, admin) = setup(&env);
 
     let depositor = Address::generate(&env);
-    assert!(client.try_deposit(&depositor, &0u64).is_err());
-    assert!(client.try_withdraw(&depositor, &0u64).is_err());
-    assert!(client.try_borrow(&admin, &0u64).is_err());
+    assert!(client.try_deposit(&depositor, &token_addr, &0u64).is_err());
+    assert!(client
+        .try_withdraw(&depositor, &token_addr, &0u64)
+        .is_err());
+    assert!(client.try_borrow(&admin, &token_addr, &0u64).is_err());
 }
 #[test]
 fn test_interest_accrual() {
@@ -331,34 +496,39 @@ fn test_interest_accrual() {
     mint_to(&env, &token_addr, &depositor, 100_000);
     mint_to(&env, &token_addr, &borrower, 100_000);
 
-    // 1. Deposit 10,000 → 10,000 shares
-    client.deposit(&depositor, &10_000u64);
+    // 1. Deposit 10,000 → 10,000 shares, minus the locked MINIMUM_LIQUIDITY
+    let depositor_shares = client.deposit(&depositor, &token_addr, &10_000u64);
 
-    // 2. Borrow 5,000
-    client.borrow(&borrower, &5_000u64);
+    // 2. Borrow 5,000 (against 80% LTV collateral)
+    client.deposit_collateral(&borrower, &token_addr, &10_000u64);
+    client.borrow(&borrower, &token_addr, &5_000u64);
 
     // 3. Jump time by 1 year (31,536,000 seconds)
     env.ledger()
         .set_timestamp(env.ledger().timestamp() + 31_536_000);
 
     // 4. Expected interest: 5,000 * 0.10 * 1 year = 500
-    let repayment_amount = client.get_repayment_amount(&borrower);
+    let repayment_amount = client.get_repayment_amount(&borrower, &token_addr);
     assert_eq!(repayment_amount, 5_500u64);
 
     // 5. Repay
-    client.repay(&borrower);
+    client.repay(&borrower, &token_addr);
 
     // 6. Verify pool state
-    let pool = client.get_pool_state();
+    let pool = client.get_pool_state(&token_addr);
     // total_deposits should be 10,000 (initial) + 500 (interest) = 10,500
     assert_eq!(pool.total_deposits, 10_500);
     assert_eq!(pool.total_borrowed, 0);
 
     // 7. Verify depositor can withdraw more than they put in
-    // shares = 10,000, pool_shares = 10,000, pool_deposits = 10,500
-    // amount = 10,000 * 10,500 / 10,000 = 10,500
-    let withdrawn = client.withdraw(&depositor, &10_000u64);
-    assert_eq!(withdrawn, 10_500);
+    // shares = 10,000 - MINIMUM_LIQUIDITY, pool_shares = 10,000,
+    // pool_deposits = 10,500
+    // amount = (10,000 - MINIMUM_LIQUIDITY) * 10,500 / 10,000
+    let withdrawn = client.withdraw(&depositor, &token_addr, &depositor_shares);
+    assert_eq!(
+        withdrawn,
+        ((depositor_shares as u128 * 10_500u128) / 10_000u128) as u64
+    );
 }
 
 #[test]
@@ -372,14 +542,335 @@ fn test_interest_precision_short_time() {
     mint_to(&env, &token_addr, &depositor, 100_000);
     mint_to(&env, &token_addr, &borrower, 100_000);
 
-    client.deposit(&depositor, &10_000u64);
-    client.borrow(&borrower, &5_000u64);
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &10_000u64);
+    client.borrow(&borrower, &token_addr, &5_000u64);
 
     // 1 hour = 3600 seconds
     // Interest = (5000 * 1000 * 3600) / (10000 * 31536000) = 18000000000 / 315360000000 ≈ 0.057
-    // Should be 0 due to truncation in simple implementation
+    // Still rounds down to 0 token for a single short interval — the fix is
+    // that the *index* itself doesn't lose this fraction (see
+    // test_interest_index_compounds_across_short_ticks below).
     env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
 
-    let repayment_amount = client.get_repayment_amount(&borrower);
+    let repayment_amount = client.get_repayment_amount(&borrower, &token_addr);
     assert_eq!(repayment_amount, 5_000u64);
 }
+
+#[test]
+fn test_interest_index_compounds_across_short_ticks() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 100_000);
+    mint_to(&env, &token_addr, &borrower, 100_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &10_000u64);
+    client.borrow(&borrower, &token_addr, &5_000u64);
+
+    // Each individual 1-hour tick accrues ≈0.057 of interest, which would
+    // round down to nothing if the rate were applied to token amounts
+    // directly at every accrual. Because the borrow index keeps the
+    // fraction in fixed-point instead, 100 one-hour ticks (a driven by an
+    // unrelated depositor action, which is what triggers `accrue`) compound
+    // into real, nonzero owed interest.
+    for _ in 0..100 {
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        client.deposit(&depositor, &token_addr, &1u64);
+    }
+
+    let repayment_amount = client.get_repayment_amount(&borrower, &token_addr);
+    assert!(repayment_amount > 5_000u64);
+}
+
+#[test]
+fn test_flash_loan_repaid_accrues_premium_to_deposits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    client.deposit(&depositor, &token_addr, &10_000u64);
+
+    let receiver_id = env.register_contract(None, flash_receiver::FlashBorrower);
+    let receiver_client = flash_receiver::FlashBorrowerClient::new(&env, &receiver_id);
+    receiver_client.init(&client.address, &true);
+
+    // receiver needs the premium on hand in addition to the borrowed amount
+    mint_to(&env, &token_addr, &receiver_id, 25); // 0.5% of 5000 = 25
+
+    let borrower = Address::generate(&env);
+    let liquidity_before = client.available_liquidity(&token_addr);
+    client.flash_loan(&borrower, &receiver_id, &token_addr, &5_000u64);
+
+    assert_eq!(client.available_liquidity(&token_addr), liquidity_before + 25);
+    let pool = client.get_pool_state(&token_addr);
+    assert_eq!(pool.total_deposits, 10_025);
+    assert_eq!(pool.total_borrowed, 0);
+}
+
+#[test]
+fn test_flash_loan_reverts_if_not_repaid() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    client.deposit(&depositor, &token_addr, &10_000u64);
+
+    let receiver_id = env.register_contract(None, flash_receiver::FlashBorrower);
+    let receiver_client = flash_receiver::FlashBorrowerClient::new(&env, &receiver_id);
+    receiver_client.init(&client.address, &false);
+
+    let borrower = Address::generate(&env);
+    let result = client.try_flash_loan(&borrower, &receiver_id, &token_addr, &5_000u64);
+    assert!(result.is_err());
+
+    // the whole call rolled back, so the pool is untouched
+    assert_eq!(client.available_liquidity(&token_addr), 10_000u64);
+}
+
+#[test]
+fn test_borrow_rejects_amount_exceeding_ltv() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &borrower, 1_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &1_000u64);
+
+    // 80% LTV on 1,000 collateral → max borrowable is 800
+    let result = client.try_borrow(&borrower, &token_addr, &801u64);
+    assert!(result.is_err());
+    assert!(client.try_borrow(&borrower, &token_addr, &800u64).is_ok());
+}
+
+#[test]
+fn test_get_obligation_reports_collateral_debt_and_health_factor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &borrower, 1_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &1_000u64);
+    client.borrow(&borrower, &token_addr, &800u64);
+
+    let obligation = client.get_obligation(&borrower, &token_addr);
+    assert_eq!(obligation.collateral, 1_000u64);
+    assert_eq!(obligation.debt, 800u64);
+    // 1,000 * 8500 / 800 = 10,625 (> 10,000 bps → still healthy)
+    assert_eq!(obligation.health_factor_bps, 10_625);
+}
+
+#[test]
+fn test_liquidate_seizes_collateral_at_a_discount_when_unhealthy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &borrower, 1_000);
+    mint_to(&env, &token_addr, &liquidator, 10_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &1_000u64);
+    client.borrow(&borrower, &token_addr, &800u64);
+
+    // Interest accrues past the point where the 85% liquidation threshold
+    // is breached: health_factor_bps = 1,000 * 8500 / debt < 10,000 once
+    // debt exceeds 850.
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 31_536_000);
+
+    let obligation = client.get_obligation(&borrower, &token_addr);
+    assert!(obligation.health_factor_bps < 10_000);
+
+    let debt = obligation.debt;
+    let seized = client.liquidate(&liquidator, &borrower, &token_addr);
+
+    // 5% bonus on top of the repaid debt
+    assert_eq!(seized, (debt * 10_500) / 10_000);
+    assert!(client.get_loan(&borrower, &token_addr).is_none());
+
+    let remaining = client.get_obligation(&borrower, &token_addr);
+    assert_eq!(remaining.collateral, 1_000 - seized);
+}
+
+#[test]
+fn test_liquidate_fails_when_healthy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &borrower, 1_000);
+    mint_to(&env, &token_addr, &liquidator, 10_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &1_000u64);
+    client.borrow(&borrower, &token_addr, &800u64);
+
+    let result = client.try_liquidate(&liquidator, &borrower, &token_addr);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_rates_flat_below_optimal_utilization() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &borrower, 10_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &10_000u64);
+    client.borrow(&borrower, &token_addr, &5_000u64); // 50% utilization, below the 80% kink
+
+    let rates = client.get_current_rates(&token_addr);
+    assert_eq!(rates.utilization_bps, 5_000);
+    assert_eq!(rates.borrow_rate_bps, 1_000); // flat base rate, slope1 = 0
+    assert_eq!(rates.supply_rate_bps, 500); // 1000 * 5000 / 10000
+}
+
+#[test]
+fn test_rates_ramp_up_past_the_kink() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &borrower, 20_000);
+
+    client.deposit(&depositor, &token_addr, &10_000u64);
+    client.deposit_collateral(&borrower, &token_addr, &20_000u64);
+    client.borrow(&borrower, &token_addr, &9_000u64); // 90% utilization, past the 80% kink
+
+    let rates = client.get_current_rates(&token_addr);
+    assert_eq!(rates.utilization_bps, 9_000);
+    // base(1000) + slope1(0) + slope2(10_000) * (9000-8000) / (10000-8000) = 1000 + 5000
+    assert_eq!(rates.borrow_rate_bps, 6_000);
+}
+
+#[test]
+fn test_first_deposit_below_minimum_reverts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+
+    let result = client.try_deposit(&depositor, &token_addr, &MINIMUM_LIQUIDITY);
+    assert!(result.is_err());
+    assert!(client
+        .try_deposit(&depositor, &token_addr, &(MINIMUM_LIQUIDITY + 1))
+        .is_ok());
+}
+
+#[test]
+fn test_first_deposit_locks_dead_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+
+    let shares = client.deposit(&depositor, &token_addr, &1000u64);
+    assert_eq!(shares, 1000 - MINIMUM_LIQUIDITY);
+
+    // The MINIMUM_LIQUIDITY dead shares are counted in total_shares but
+    // never credited to the depositor, so they are permanently locked.
+    let pool = client.get_pool_state(&token_addr);
+    assert_eq!(pool.total_shares, 1000);
+    assert_eq!(
+        client.get_shares_of(&depositor, &token_addr),
+        1000 - MINIMUM_LIQUIDITY
+    );
+}
+
+#[test]
+fn test_donation_then_tiny_deposit_still_credits_proportional_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let victim = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &attacker, 1_000_000);
+    mint_to(&env, &token_addr, &victim, 10_000);
+
+    client.deposit(&depositor, &token_addr, &1000u64);
+
+    // Attacker donates a huge balance directly into the pool's token
+    // account, bypassing `deposit` entirely, to try to inflate the share
+    // price ahead of the victim's deposit.
+    tok_client(&env, &token_addr).transfer(&attacker, &client.address, &1_000_000);
+
+    // Share price is derived from the internally tracked `total_deposits`
+    // ledger rather than the contract's raw token balance, so the
+    // donation has no effect: the victim is still credited shares
+    // proportional to the pool's real (non-donated) deposits.
+    let victim_shares = client.deposit(&victim, &token_addr, &10u64);
+    assert_eq!(victim_shares, 10u64);
+}
+
+#[test]
+fn test_deposit_reverts_when_shares_would_round_to_zero() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, _admin) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let victim = Address::generate(&env);
+    mint_to(&env, &token_addr, &depositor, 10_000);
+    mint_to(&env, &token_addr, &victim, 10_000);
+
+    // First deposit just above the minimum: the depositor gets 1 share,
+    // MINIMUM_LIQUIDITY dead shares are locked forever.
+    let shares = client.deposit(&depositor, &token_addr, &(MINIMUM_LIQUIDITY + 1));
+    assert_eq!(shares, 1u64);
+
+    fund_collateral(&env, &client, &token_addr, &borrower, MINIMUM_LIQUIDITY);
+    client.borrow(&borrower, &token_addr, &MINIMUM_LIQUIDITY);
+
+    // A huge stretch of accrued interest inflates total_deposits far past
+    // total_shares, without minting any new shares.
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 1000 * SECONDS_PER_YEAR);
+
+    // The victim's small deposit would now round down to zero shares —
+    // it must revert instead of silently donating their tokens to the pool.
+    let result = client.try_deposit(&victim, &token_addr, &1u64);
+    assert!(result.is_err());
+}